@@ -1,9 +1,11 @@
 use std::fmt::Display;
 
-use anyhow::Result;
-use chrono::{DateTime, Local, Utc};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
 use clap::{Parser, ValueEnum};
 use clap_verbosity_flag::Verbosity;
+use serde::Serialize;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
@@ -27,6 +29,38 @@ struct Cli {
     #[arg(short, long)]
     now: bool,
 
+    /// Additional IANA time zone to display, e.g. `America/New_York`.
+    ///
+    /// Can be passed multiple times to show several zones at once.
+    #[arg(short = 'z', long = "timezone", value_parser = parse_timezone)]
+    timezone: Vec<Tz>,
+
+    /// How to render each resulting timestamp.
+    ///
+    /// Defaults to a pretty-printed debug dump of every representation; the
+    /// other variants emit a single RFC3339 line at the chosen precision.
+    #[arg(short, long, default_value_t = Output::default())]
+    output: Output,
+
+    /// Render each resulting timestamp with a custom `chrono` strftime layout.
+    ///
+    /// Takes precedence over `--output` when set.
+    #[arg(long)]
+    strftime: Option<String>,
+
+    /// Emit the result(s) as JSON instead of the `--output`/`--strftime` rendering.
+    ///
+    /// A single input produces one object; multiple CSV values produce an array.
+    #[arg(long)]
+    json: bool,
+
+    /// Show the signed duration between successive timestamps.
+    ///
+    /// With a single input the delta is taken against the current system time,
+    /// answering "how long ago was this epoch?".
+    #[arg(short, long)]
+    diff: bool,
+
     #[clap(flatten)]
     pub verbosity: Verbosity,
 }
@@ -34,20 +68,87 @@ struct Cli {
 #[derive(ValueEnum, Default, Clone, Debug)]
 #[allow(non_camel_case_types)]
 enum Format {
+    /// Infer the epoch precision from the value's magnitude.
     #[default]
+    auto,
     seconds,
     milliseconds,
     microseconds,
     nanoseconds,
+    /// A calendar string rather than an epoch, e.g. `2024-09-10T00:19:08Z`.
+    rfc3339,
 }
 
 impl Format {
     fn symbol(&self) -> String {
         match self {
+            Format::auto => "auto".to_string(),
             Format::seconds => "s".to_string(),
             Format::milliseconds => "ms".to_string(),
             Format::microseconds => "Î¼s".to_string(),
             Format::nanoseconds => "ns".to_string(),
+            Format::rfc3339 => "rfc3339".to_string(),
+        }
+    }
+
+    /// Infer the epoch precision of `ts` from its magnitude.
+    ///
+    /// The thresholds are chosen so that any plausible modern date maps to the
+    /// right unit: seconds stay below ~1e11 (covering ~2001–5138), and each
+    /// further unit shifts the ceiling up by three decimal digits. Negative
+    /// (pre-epoch) values are classified by their absolute magnitude, and a
+    /// value sitting exactly on a boundary takes the lower-precision unit.
+    fn detect(ts: i64) -> Format {
+        match ts.unsigned_abs() {
+            n if n <= 100_000_000_000 => Format::seconds,
+            n if n <= 100_000_000_000_000 => Format::milliseconds,
+            n if n <= 100_000_000_000_000_000 => Format::microseconds,
+            _ => Format::nanoseconds,
+        }
+    }
+
+    /// Build a [`DateTime<Utc>`] from an integer epoch `ts` at this precision.
+    fn from_epoch(&self, ts: i64) -> Result<DateTime<Utc>> {
+        match self {
+            Format::seconds => DateTime::<Utc>::from_timestamp(ts, 0)
+                .context("input should be a valid time"),
+            Format::milliseconds => DateTime::<Utc>::from_timestamp_millis(ts)
+                .context("input should be a valid time"),
+            Format::microseconds => DateTime::<Utc>::from_timestamp_micros(ts)
+                .context("input should be a valid time"),
+            Format::nanoseconds => Ok(DateTime::<Utc>::from_timestamp_nanos(ts)),
+            Format::auto | Format::rfc3339 => {
+                anyhow::bail!("{self} is not an integer epoch format")
+            }
+        }
+    }
+
+    /// Parse a single `timestamp` value into a [`DateTime<Utc>`].
+    ///
+    /// Returns the value alongside the *effective* format, which differs from
+    /// `self` only for [`Format::auto`], where the precision is inferred from
+    /// the value. The epoch formats interpret the value as an integer offset
+    /// from the unix epoch, while [`Format::rfc3339`] parses a calendar string.
+    /// A `YYYY-MM-DDTHH:MM:SS` string without an offset is assumed to be UTC;
+    /// other offset-less layouts are rejected. This mirrors VRL's
+    /// `from_unix_timestamp` handling.
+    fn parse(&self, val: &str) -> Result<(DateTime<Utc>, Format)> {
+        match self {
+            Format::auto => {
+                let effective = Format::detect(val.parse()?);
+                Ok((effective.from_epoch(val.parse()?)?, effective))
+            }
+            Format::rfc3339 => {
+                let dt = DateTime::parse_from_rfc3339(val)
+                    .map(DateTime::<Utc>::from)
+                    .or_else(|_| {
+                        NaiveDateTime::parse_from_str(val, "%Y-%m-%dT%H:%M:%S")
+                            .map(|naive| naive.and_utc())
+                    })
+                    .with_context(|| format!("\"{val}\" is not a valid rfc3339 timestamp"))?;
+                Ok((dt, Format::rfc3339))
+            }
+            epoch => Ok((epoch.from_epoch(val.parse()?)?, epoch.clone())),
         }
     }
 }
@@ -58,25 +159,183 @@ impl Display for Format {
     }
 }
 
-#[derive(Debug)]
+#[derive(ValueEnum, Default, Clone, Debug)]
+#[allow(non_camel_case_types)]
+enum Output {
+    /// Pretty-printed debug dump of every representation.
+    #[default]
+    debug,
+    seconds,
+    millis,
+    micros,
+    nanos,
+}
+
+impl Output {
+    /// The RFC3339 precision tier for this output, or `None` for the debug dump.
+    fn seconds_format(&self) -> Option<SecondsFormat> {
+        match self {
+            Output::debug => None,
+            Output::seconds => Some(SecondsFormat::Secs),
+            Output::millis => Some(SecondsFormat::Millis),
+            Output::micros => Some(SecondsFormat::Micros),
+            Output::nanos => Some(SecondsFormat::Nanos),
+        }
+    }
+}
+
+impl Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Parse an IANA time zone name, surfacing a readable error for unknown zones.
+fn parse_timezone(val: &str) -> Result<Tz, String> {
+    val.parse()
+        .map_err(|_| format!("\"{val}\" is not a known IANA time zone"))
+}
+
+/// Render the magnitude of a [`Duration`] as human-readable text, e.g. `2h 3m 4s`.
+///
+/// The absolute value is decomposed into days/hours/minutes/seconds and the
+/// nonzero components are joined. The sign is intentionally dropped here;
+/// callers add the phrasing appropriate to their comparison (see
+/// [`relative_to_now`] and [`between_stamps`]).
+fn humanize_duration(delta: Duration) -> String {
+    let mut remaining = delta.num_seconds().abs();
+    let days = remaining / 86_400;
+    remaining %= 86_400;
+    let hours = remaining / 3_600;
+    remaining %= 3_600;
+    let minutes = remaining / 60;
+    let seconds = remaining % 60;
+
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(format!("{days}d"));
+    }
+    if hours > 0 {
+        parts.push(format!("{hours}h"));
+    }
+    if minutes > 0 {
+        parts.push(format!("{minutes}m"));
+    }
+    if seconds > 0 || parts.is_empty() {
+        parts.push(format!("{seconds}s"));
+    }
+    parts.join(" ")
+}
+
+/// Phrase a delta taken against the current time: `2h ago` / `in 2h`.
+fn relative_to_now(delta: Duration) -> String {
+    let magnitude = humanize_duration(delta);
+    if delta < Duration::zero() {
+        format!("in {magnitude}")
+    } else {
+        format!("{magnitude} ago")
+    }
+}
+
+/// Phrase a delta between two input timestamps: `2h earlier` / `2h later`.
+fn between_stamps(delta: Duration) -> String {
+    if delta.is_zero() {
+        return "same time".to_string();
+    }
+    let magnitude = humanize_duration(delta);
+    if delta < Duration::zero() {
+        format!("{magnitude} earlier")
+    } else {
+        format!("{magnitude} later")
+    }
+}
+
+/// Print a single delta line with both the humanized and raw representations.
+fn print_diff(label: String, delta: Duration, human: String) {
+    println!(
+        "{label}: {human} ({s}s / {ms}ms)",
+        s = delta.num_seconds(),
+        ms = delta.num_milliseconds(),
+    );
+}
+
+#[derive(Debug, Serialize)]
 pub struct Times {
     pub local: DateTime<Local>,
     pub utc: DateTime<Utc>,
+    pub zoned: Vec<DateTime<Tz>>,
     pub unix_s: i64,
     pub unix_ms: i64,
+    pub unix_us: i64,
+    pub unix_ns: Option<i64>,
 }
 
 impl Times {
-    pub fn new(dt: DateTime<Utc>) -> Self {
+    pub fn new(dt: DateTime<Utc>, timezones: &[Tz]) -> Self {
         Self {
             local: DateTime::from(dt),
             utc: dt,
+            zoned: timezones.iter().map(|tz| dt.with_timezone(tz)).collect(),
             unix_s: dt.timestamp(),
             unix_ms: dt.timestamp_millis(),
+            unix_us: dt.timestamp_micros(),
+            unix_ns: dt.timestamp_nanos_opt(),
+        }
+    }
+
+    /// Render these times according to the selected `output`.
+    ///
+    /// A `strftime` layout, when present, wins over `output` and runs each
+    /// time through chrono's `format()`. Otherwise an RFC3339 precision tier
+    /// produces a single line, and the default falls back to the debug dump.
+    /// The single-line modes render the UTC time followed by any `-z` zones so
+    /// requested zones are never silently dropped.
+    fn render(&self, output: &Output, strftime: Option<&str>) -> String {
+        match (strftime, output.seconds_format()) {
+            // The debug dump already includes `local`, `utc` and every zone.
+            (None, None) => format!("{self:#?}"),
+            (strftime, seconds_format) => {
+                let mut parts = vec![render_dt(&self.utc, seconds_format, strftime)];
+                for zoned in &self.zoned {
+                    parts.push(render_dt(zoned, seconds_format, strftime));
+                }
+                parts.join("  ")
+            }
         }
     }
 }
 
+/// Render a single [`DateTime`] in a single-line `--output`/`--strftime` mode.
+///
+/// A `strftime` layout takes precedence; otherwise `seconds_format` selects the
+/// RFC3339 precision tier. At least one of the two must be set — guaranteed by
+/// the caller in [`Times::render`].
+fn render_dt<Tz2>(
+    dt: &DateTime<Tz2>,
+    seconds_format: Option<SecondsFormat>,
+    strftime: Option<&str>,
+) -> String
+where
+    Tz2: TimeZone,
+    Tz2::Offset: Display,
+{
+    match strftime {
+        Some(fmt) => dt.format(fmt).to_string(),
+        None => dt.to_rfc3339_opts(
+            seconds_format.expect("render_dt requires strftime or a seconds_format"),
+            true,
+        ),
+    }
+}
+
+/// A [`Times`] tagged with the format symbol it was produced from, for `--json`.
+#[derive(Debug, Serialize)]
+struct TimesRecord {
+    format: String,
+    #[serde(flatten)]
+    times: Times,
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -87,43 +346,140 @@ fn main() -> Result<()> {
     log::debug!("{cli:#?}");
 
     if let Some(timestamps) = &cli.timestamp {
-        timestamps
+        let results: Vec<(&String, Times, Format)> = timestamps
             .iter()
             .filter_map(|val| {
                 log::trace!("filter_mapping: {val}");
                 if val.trim().is_empty() {
                     return None;
                 }
-                match val.parse::<i64>() {
-                    Ok(parsed) => return Some(parsed),
+                match cli.format.parse(val) {
+                    Ok((in_time, effective)) => {
+                        Some((val, Times::new(in_time, &cli.timezone), effective))
+                    }
                     Err(err) => {
-                        log::error!("Could not convert \"{val}\" into i64: {err:?}");
-                        return None;
+                        log::error!("Could not parse \"{val}\": {err:?}");
+                        None
                     }
                 }
             })
-            .for_each(|ts| {
-                let in_time = match cli.format {
-                    Format::seconds => DateTime::<Utc>::from_timestamp(ts, 0)
-                        .expect("input should be a valid time"),
-                    Format::milliseconds => DateTime::<Utc>::from_timestamp_millis(ts)
-                        .expect("input should be a valid time"),
-                    Format::microseconds => DateTime::<Utc>::from_timestamp_micros(ts)
-                        .expect("input should be a valid time"),
-                    Format::nanoseconds => DateTime::<Utc>::from_timestamp_nanos(ts),
-                };
+            .collect();
 
+        if cli.diff {
+            let stamps: Vec<(&String, DateTime<Utc>)> =
+                results.iter().map(|(val, times, _)| (*val, times.utc)).collect();
+            match stamps.as_slice() {
+                [] => {}
+                [(val, dt)] => {
+                    // Only one value: compare it against the current time.
+                    let delta = Utc::now().signed_duration_since(*dt);
+                    print_diff(format!("{val} vs now"), delta, relative_to_now(delta));
+                }
+                many => {
+                    for pair in many.windows(2) {
+                        let (a_val, a) = pair[0];
+                        let (b_val, b) = pair[1];
+                        let delta = b.signed_duration_since(a);
+                        print_diff(format!("{a_val} -> {b_val}"), delta, between_stamps(delta));
+                    }
+                }
+            }
+        } else if cli.json {
+            let records: Vec<TimesRecord> = results
+                .into_iter()
+                .map(|(_, times, effective)| TimesRecord {
+                    format: effective.symbol(),
+                    times,
+                })
+                .collect();
+            // A lone input serializes as an object; several become an array.
+            let inputs = timestamps.iter().filter(|val| !val.trim().is_empty()).count();
+            let json = match (inputs, records.as_slice()) {
+                (1, [record]) => serde_json::to_string_pretty(record)?,
+                _ => serde_json::to_string_pretty(&records)?,
+            };
+            println!("{json}");
+        } else {
+            results.iter().for_each(|(val, times, effective)| {
                 println!(
-                    "({ts} {sym}): {time:#?}",
-                    sym = cli.format.symbol(),
-                    time = Times::new(in_time)
+                    "({val} {sym}): {time}",
+                    sym = effective.symbol(),
+                    time = times.render(&cli.output, cli.strftime.as_deref())
                 );
-            })
+            });
+        }
     }
 
     if cli.now {
-        println!("(now): {:#?}", Times::new(chrono::offset::Utc::now()));
+        let times = Times::new(chrono::offset::Utc::now(), &cli.timezone);
+        if cli.json {
+            let record = TimesRecord {
+                format: "now".to_string(),
+                times,
+            };
+            println!("{}", serde_json::to_string_pretty(&record)?);
+        } else {
+            println!("(now): {}", times.render(&cli.output, cli.strftime.as_deref()));
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_buckets_by_magnitude() {
+        assert_eq!(Format::detect(1_725_932_348).symbol(), "s");
+        assert_eq!(Format::detect(1_725_932_348_000).symbol(), "ms");
+        assert_eq!(Format::detect(1_725_932_348_000_000).symbol(), "Î¼s");
+        assert_eq!(Format::detect(1_725_932_348_000_000_000).symbol(), "ns");
+    }
+
+    #[test]
+    fn detect_treats_boundary_as_lower_precision() {
+        assert_eq!(Format::detect(100_000_000_000).symbol(), "s");
+        assert_eq!(Format::detect(100_000_000_001).symbol(), "ms");
+        assert_eq!(Format::detect(100_000_000_000_000).symbol(), "ms");
+        assert_eq!(Format::detect(100_000_000_000_001).symbol(), "Î¼s");
+        assert_eq!(Format::detect(i64::MAX).symbol(), "ns");
+    }
+
+    #[test]
+    fn detect_classifies_negative_epochs_by_absolute_value() {
+        assert_eq!(Format::detect(-1_725_932_348).symbol(), "s");
+        assert_eq!(Format::detect(-1_725_932_348_000).symbol(), "ms");
+        assert_eq!(Format::detect(i64::MIN).symbol(), "ns");
+    }
+
+    #[test]
+    fn humanize_decomposes_into_nonzero_components() {
+        assert_eq!(
+            humanize_duration(Duration::seconds(2 * 3_600 + 3 * 60 + 4)),
+            "2h 3m 4s"
+        );
+        assert_eq!(humanize_duration(Duration::days(5)), "5d");
+        assert_eq!(humanize_duration(Duration::seconds(4)), "4s");
+    }
+
+    #[test]
+    fn humanize_zero_and_negative_use_magnitude_only() {
+        assert_eq!(humanize_duration(Duration::zero()), "0s");
+        assert_eq!(humanize_duration(Duration::seconds(-3_661)), "1h 1m 1s");
+    }
+
+    #[test]
+    fn relative_to_now_reserves_ago_and_in() {
+        assert_eq!(relative_to_now(Duration::hours(1)), "1h ago");
+        assert_eq!(relative_to_now(Duration::hours(-1)), "in 1h");
+    }
+
+    #[test]
+    fn between_stamps_uses_earlier_later_and_same_time() {
+        assert_eq!(between_stamps(Duration::hours(1)), "1h later");
+        assert_eq!(between_stamps(Duration::hours(-1)), "1h earlier");
+        assert_eq!(between_stamps(Duration::zero()), "same time");
+    }
+}